@@ -0,0 +1,187 @@
+//! Cross-cutting Rocket fairing enforcing CORS, security headers and response compression
+//! uniformly across every route (and error catcher), instead of per-handler header juggling.
+
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::Header,
+    Request, Response,
+};
+use std::{env, io::Read};
+
+/// Env var with a comma-separated list of allowed CORS origins. Unset or empty means "allow any
+/// origin" (mirrors how permissive this API already is without authentication).
+const CORS_ORIGINS_ENV: &str = "GOOUT_CORS_ALLOWED_ORIGINS";
+/// Env var toggling response compression. Defaults to enabled.
+const COMPRESSION_ENABLED_ENV: &str = "GOOUT_COMPRESSION_ENABLED";
+/// Env var with the minimum response body size, in bytes, before compression is applied. Below
+/// this, compression overhead (both CPU and the codec's own framing) outweighs the savings.
+const COMPRESSION_MIN_BYTES_ENV: &str = "GOOUT_COMPRESSION_MIN_BYTES";
+const DEFAULT_COMPRESSION_MIN_BYTES: usize = 1024;
+
+/// Codecs we can compress responses with, in order of preference when the client's
+/// `Accept-Encoding` quality values tie: zstd for its speed/ratio tradeoff, then brotli for its
+/// ratio, then gzip for maximum compatibility.
+const SUPPORTED_ENCODINGS: [&str; 3] = ["zstd", "br", "gzip"];
+
+/// Fairing that decorates every outgoing response with CORS headers, baseline security headers,
+/// and negotiated `Accept-Encoding` compression. Runs on the response phase, so it also applies to
+/// the JSON error bodies produced by the [crate::response] catchers.
+pub(crate) struct ApiPolicyFairing;
+
+impl Fairing for ApiPolicyFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "API cross-cutting policy (CORS, security headers, compression)",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        set_cors_headers(request, response);
+        set_security_headers(response);
+        compress_if_supported(request, response);
+    }
+}
+
+fn allowed_origins() -> Vec<String> {
+    env::var(CORS_ORIGINS_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn set_cors_headers(request: &Request<'_>, response: &mut Response<'_>) {
+    let origins = allowed_origins();
+    let requested_origin = request.headers().get_one("Origin");
+
+    let allow_origin = match requested_origin {
+        _ if origins.is_empty() => "*",
+        Some(origin) if origins.iter().any(|allowed| allowed == origin) => origin,
+        _ => return,
+    };
+
+    response.set_header(Header::new("Access-Control-Allow-Origin", allow_origin.to_owned()));
+    response.set_header(Header::new("Access-Control-Allow-Methods", "GET, OPTIONS"));
+    response.set_header(Header::new("Vary", "Origin"));
+}
+
+fn set_security_headers(response: &mut Response<'_>) {
+    response.set_header(Header::new("X-Content-Type-Options", "nosniff"));
+    response.set_header(Header::new("X-Frame-Options", "DENY"));
+    response.set_header(Header::new("Permissions-Policy", "geolocation=(), camera=(), microphone=()"));
+}
+
+fn compression_enabled() -> bool {
+    env::var(COMPRESSION_ENABLED_ENV).map(|value| value != "false" && value != "0").unwrap_or(true)
+}
+
+fn compression_min_bytes() -> usize {
+    env::var(COMPRESSION_MIN_BYTES_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_COMPRESSION_MIN_BYTES)
+}
+
+/// Negotiate the most-preferred codec out of the client's `Accept-Encoding` header, honouring
+/// `q` quality values and falling back to [SUPPORTED_ENCODINGS] order on ties.
+fn negotiate_encoding(accept_encoding: &str) -> Option<&'static str> {
+    let mut best: Option<(&'static str, f32)> = None;
+
+    for entry in accept_encoding.split(',') {
+        let mut parts = entry.split(';').map(str::trim);
+        let coding = match parts.next() {
+            Some(coding) => coding,
+            None => continue,
+        };
+        let encoding = match SUPPORTED_ENCODINGS.iter().find(|&&supported| supported == coding) {
+            Some(&encoding) => encoding,
+            None => continue,
+        };
+
+        let quality =
+            parts.find_map(|param| param.strip_prefix("q=")).and_then(|q| q.parse::<f32>().ok()).unwrap_or(1.0);
+        if quality <= 0.0 {
+            continue;
+        }
+
+        let is_better = match best {
+            None => true,
+            Some((_, best_quality)) if quality > best_quality => true,
+            Some((best_encoding, best_quality)) if (quality - best_quality).abs() < f32::EPSILON => {
+                encoding_preference(encoding) < encoding_preference(best_encoding)
+            }
+            _ => false,
+        };
+        if is_better {
+            best = Some((encoding, quality));
+        }
+    }
+
+    best.map(|(encoding, _)| encoding)
+}
+
+fn encoding_preference(encoding: &str) -> usize {
+    SUPPORTED_ENCODINGS.iter().position(|&supported| supported == encoding).unwrap_or(usize::MAX)
+}
+
+fn compress_if_supported<'r>(request: &Request<'_>, response: &mut Response<'r>) {
+    if !compression_enabled() || response.headers().contains("Content-Encoding") {
+        return;
+    }
+
+    let accept_encoding = request.headers().get_one("Accept-Encoding").unwrap_or_default();
+    let encoding = match negotiate_encoding(accept_encoding) {
+        Some(encoding) => encoding,
+        None => return,
+    };
+
+    let mut body = Vec::new();
+    if response.body_mut().as_reader().read_to_end(&mut body).is_err() {
+        return;
+    }
+
+    if body.len() < compression_min_bytes() {
+        response.set_sized_body(std::io::Cursor::new(body));
+        return;
+    }
+
+    let compressed = match encoding {
+        "gzip" => gzip_compress(&body),
+        "br" => brotli_compress(&body),
+        "zstd" => zstd_compress(&body),
+        _ => None,
+    };
+
+    // On compression failure, fall back to the uncompressed body rather than send bytes
+    // mislabeled with a `Content-Encoding` the client can't actually decode.
+    match compressed {
+        Some(compressed) => {
+            response.set_sized_body(std::io::Cursor::new(compressed));
+            response.set_raw_header("Content-Encoding", encoding);
+        }
+        None => response.set_sized_body(std::io::Cursor::new(body)),
+    }
+}
+
+fn gzip_compress(body: &[u8]) -> Option<Vec<u8>> {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body).ok()?;
+    encoder.finish().ok()
+}
+
+fn brotli_compress(body: &[u8]) -> Option<Vec<u8>> {
+    let mut compressed = Vec::new();
+    let mut input = body;
+    brotli::BrotliCompress(&mut input, &mut compressed, &brotli::enc::BrotliEncoderParams::default()).ok()?;
+    Some(compressed)
+}
+
+fn zstd_compress(body: &[u8]) -> Option<Vec<u8>> {
+    zstd::stream::encode_all(body, 0).ok()
+}
@@ -1,9 +1,19 @@
 //! Handlers for `/city/*` endpoints.
 
 use crate::{
-    response::{ErrorResponse::BadRequest, HandlerResult, JsonResult},
-    services::locations_repo::{Coordinates, ElasticCity, Language, LocationsElasticRepository},
-    stateful::elasticsearch::WithElastic,
+    response::{
+        CachedJson, CachedJsonResult, ErrorResponse,
+        ErrorResponse::InternalServerError,
+        HandlerResult, JsonResult,
+    },
+    services::locations_repo::{
+        resolve_name, Boundary, BoundingBox, Coordinates, ElasticCity, ElasticCitySuggestion, ElasticRegion, Facets,
+        HighlightOptions, Language, LocationsElasticRepository, MatchingStrategy, Pagination,
+    },
+    stateful::{
+        cache::{ttl_secs, ResponseCache},
+        elasticsearch::WithElastic,
+    },
     AppState,
 };
 use actix_web::{
@@ -11,10 +21,10 @@ use actix_web::{
     web::{Data, Json, Query},
     HttpRequest,
 };
-use futures::{stream::FuturesOrdered, TryStreamExt};
+use once_cell::sync::Lazy;
 use paperclip::actix::{api_v2_operation, Apiv2Schema};
 use serde::{Deserialize, Serialize};
-use std::cmp::Reverse;
+use std::{cmp::Reverse, collections::HashMap};
 use validator::Validate;
 
 /// Query for the `/city/v1/get` endpoint.
@@ -39,52 +49,159 @@ pub(crate) struct CityResponse {
     name: String,
     /// E.g. `"Plzeňský kraj"`.
     regionName: String,
+    /// `name` with the matched fragment wrapped in the requested highlight tags, e.g.
+    /// `"<em>Plze</em>ň"`. Only set on `/city/v1/search` results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    highlightedName: Option<String>,
 }
 
 /// The `/city/v1/get` endpoint. HTTP request: [`CityQuery`], response: [`CityResponse`].
 ///
-/// Get city of given ID localized to given language.
+/// Get city of given ID localized to given language. Cached: honors `If-None-Match` and emits
+/// `Cache-Control`/`ETag` headers, see [CachedJson].
 #[api_v2_operation]
-pub(crate) async fn get(query: Query<CityQuery>, app: Data<AppState>) -> JsonResult<CityResponse> {
-    let locations_es_repo = LocationsElasticRepository(app.get_ref());
-    let es_city = locations_es_repo.get_city(query.id).await?;
+pub(crate) async fn get(
+    request: HttpRequest,
+    query: Query<CityQuery>,
+    app: Data<AppState>,
+) -> CachedJsonResult {
+    static CACHE: Lazy<ResponseCache<(u64, Language)>> = Lazy::new(ResponseCache::new);
+
+    let cached = CACHE
+        .get_or_compute((query.id, query.language), || async {
+            let locations_es_repo = LocationsElasticRepository(app.get_ref());
+            let es_city = locations_es_repo.get_city(query.id).await?;
+            let resp = es_city.into_resp_single(app.get_ref(), query.language).await?;
+            serde_json::to_string(&resp).map_err(|e| InternalServerError(e.to_string()))
+        })
+        .await?;
 
-    Ok(Json(es_city.into_resp(app.get_ref(), query.language).await?))
+    let if_none_match = get_header_str(request.headers(), "If-None-Match");
+    Ok(CachedJson::new(cached, ttl_secs(), if_none_match))
 }
 
 /// Query for the `/city/v1/featured` endpoint.
+#[allow(non_snake_case)]
 #[derive(Apiv2Schema, Deserialize)]
 pub(crate) struct FeaturedQuery {
     language: Language,
+    /// Number of hits to skip. Mutually exclusive with `page`/`hitsPerPage`. Defaults to 0.
+    offset: Option<u32>,
+    /// Maximum number of hits to return. Mutually exclusive with `page`/`hitsPerPage`. Defaults to 10.
+    limit: Option<u32>,
+    /// 1-based page number. Mutually exclusive with `offset`/`limit`.
+    page: Option<u32>,
+    /// Number of hits per page. Mutually exclusive with `offset`/`limit`. Defaults to 10.
+    hitsPerPage: Option<u32>,
+}
+
+impl FeaturedQuery {
+    /// Parse this query's pagination parameters into a [Pagination], see [parse_pagination].
+    fn pagination(&self) -> HandlerResult<Pagination> {
+        parse_pagination(self.offset, self.limit, self.page, self.hitsPerPage)
+    }
 }
 
-/// A list of `City` API entities.
+/// A page of `City` API entities.
+#[allow(non_snake_case)]
 #[derive(Apiv2Schema, Serialize)]
 pub(crate) struct MultiCityResponse {
     cities: Vec<CityResponse>,
+    /// Total number of hits matching the query, regardless of pagination. Only set by paginated
+    /// endpoints (`/city/v1/search`, `/city/v1/featured`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimatedTotalHits: Option<u64>,
+    /// Offset of the first hit in `cities`. Only set by paginated endpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u32>,
+    /// Maximum number of hits that were requested. Only set by paginated endpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    /// Total number of pages. Only set when the request used `page`/`hitsPerPage` pagination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    totalPages: Option<u32>,
+    /// Hit counts per requested facet field and value, see [SearchQuery::facets]. Only set when
+    /// facets were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    facetDistribution: Option<HashMap<String, HashMap<String, u64>>>,
+}
+
+impl MultiCityResponse {
+    /// Build a response for an endpoint that does not support pagination.
+    fn unpaginated(cities: Vec<CityResponse>) -> Self {
+        Self { cities, estimatedTotalHits: None, offset: None, limit: None, totalPages: None, facetDistribution: None }
+    }
+
+    /// Build a response out of a [crate::services::locations_repo::PagedResult]'s metadata.
+    fn paginated(
+        cities: Vec<CityResponse>,
+        estimated_total_hits: u64,
+        offset: u32,
+        limit: u32,
+        total_pages: Option<u32>,
+    ) -> Self {
+        Self {
+            cities,
+            estimatedTotalHits: Some(estimated_total_hits),
+            offset: Some(offset),
+            limit: Some(limit),
+            totalPages: total_pages,
+            facetDistribution: None,
+        }
+    }
+
+    /// Attach facet hit counts to this response, see [LocationsElasticRepository::search]. A no-op
+    /// if `facet_distribution` is empty (no facets were requested).
+    fn with_facet_distribution(mut self, facet_distribution: HashMap<String, HashMap<String, u64>>) -> Self {
+        self.facetDistribution = if facet_distribution.is_empty() { None } else { Some(facet_distribution) };
+        self
+    }
 }
 
 /// The `/city/v1/featured` endpoint. HTTP request: [`FeaturedQuery`], response: [`MultiCityResponse`].
 ///
-/// Returns a list of all featured cities.
+/// Returns a page of featured cities. Cached: honors `If-None-Match` and emits
+/// `Cache-Control`/`ETag` headers, see [CachedJson].
 #[api_v2_operation]
 pub(crate) async fn featured(
+    request: HttpRequest,
     query: Query<FeaturedQuery>,
     app: Data<AppState>,
-) -> JsonResult<MultiCityResponse> {
-    let locations_es_repo = LocationsElasticRepository(app.get_ref());
-    let mut es_cities = locations_es_repo.get_featured_cities().await?;
-
-    let preferred_country_iso = match query.language {
-        Language::CS => "CZ",
-        Language::DE => "DE",
-        Language::EN => "CZ",
-        Language::PL => "PL",
-        Language::SK => "SK",
-    };
-    es_cities.sort_by_key(|c| Reverse(c.countryIso == preferred_country_iso));
+) -> CachedJsonResult {
+    static CACHE: Lazy<ResponseCache<(Language, Option<u32>, Option<u32>, Option<u32>, Option<u32>)>> =
+        Lazy::new(ResponseCache::new);
 
-    es_cities_into_resp(app.get_ref(), es_cities, query.language).await
+    let cache_key = (query.language, query.offset, query.limit, query.page, query.hitsPerPage);
+    let cached = CACHE
+        .get_or_compute(cache_key, || async {
+            let locations_es_repo = LocationsElasticRepository(app.get_ref());
+            let pagination = query.pagination()?;
+            let mut paged = locations_es_repo.get_featured_cities(pagination).await?;
+
+            let preferred_country_iso = match query.language {
+                Language::CS => "CZ",
+                Language::DE => "DE",
+                Language::EN => "CZ",
+                Language::PL => "PL",
+                Language::SK => "SK",
+            };
+            // Note this only re-orders within the current page, unlike before pagination existed.
+            paged.items.sort_by_key(|c| Reverse(c.countryIso == preferred_country_iso));
+
+            let cities = es_cities_into_city_responses(app.get_ref(), paged.items, query.language).await?;
+            let resp = MultiCityResponse::paginated(
+                cities,
+                paged.estimated_total_hits,
+                paged.offset,
+                paged.limit,
+                paged.total_pages,
+            );
+            serde_json::to_string(&resp).map_err(|e| InternalServerError(e.to_string()))
+        })
+        .await?;
+
+    let if_none_match = get_header_str(request.headers(), "If-None-Match");
+    Ok(CachedJson::new(cached, ttl_secs(), if_none_match))
 }
 
 /// Query for the `/city/v1/search` endpoint.
@@ -95,26 +212,268 @@ pub(crate) struct SearchQuery {
     query: String,
     /// ISO 3166-1 alpha-2 country code. Can be used to limit scope of the search to a given country.
     countryIso: Option<String>,
+    /// Southern edge latitude of a viewport to limit scope of the search to, in decimal degrees.
+    minLat: Option<f64>,
+    /// Western edge longitude of a viewport to limit scope of the search to, in decimal degrees.
+    minLon: Option<f64>,
+    /// Northern edge latitude of a viewport to limit scope of the search to, in decimal degrees.
+    maxLat: Option<f64>,
+    /// Eastern edge longitude of a viewport to limit scope of the search to, in decimal degrees.
+    maxLon: Option<f64>,
     language: Language,
+    /// Opening tag wrapped around the matched fragment of the returned name. Defaults to `"<em>"`.
+    highlightPreTag: Option<String>,
+    /// Closing tag wrapped around the matched fragment of the returned name. Defaults to `"</em>"`.
+    highlightPostTag: Option<String>,
+    /// Maximum length, in characters, of the highlighted name fragment before it is cropped.
+    /// Defaults to 100.
+    cropLength: Option<u32>,
+    /// Marker appended to a highlighted name fragment that got cropped. Defaults to `"…"`.
+    cropMarker: Option<String>,
+    /// Number of hits to skip. Mutually exclusive with `page`/`hitsPerPage`. Defaults to 0.
+    offset: Option<u32>,
+    /// Maximum number of hits to return. Mutually exclusive with `page`/`hitsPerPage`. Defaults to 10.
+    limit: Option<u32>,
+    /// 1-based page number. Mutually exclusive with `offset`/`limit`.
+    page: Option<u32>,
+    /// Number of hits per page. Mutually exclusive with `offset`/`limit`. Defaults to 10.
+    hitsPerPage: Option<u32>,
+    /// Comma-separated list of fields to return hit counts for, e.g. `countryIso`. Currently
+    /// supports `countryIso` and `regionId`. Defaults to none.
+    facets: Option<String>,
+    /// Whether every term of `query` must match (`all`) or a hit matching any term is returned,
+    /// ranked higher the more terms it matches (`last`). Defaults to `last`.
+    matchingStrategy: Option<String>,
+}
+
+impl SearchQuery {
+    /// Extract optional bounding box out of query, error if only some corners are given.
+    fn bounding_box(&self) -> HandlerResult<Option<BoundingBox>> {
+        match (self.minLat, self.minLon, self.maxLat, self.maxLon) {
+            (Some(min_lat), Some(min_lon), Some(max_lat), Some(max_lon)) => {
+                BoundingBox::new(min_lat, min_lon, max_lat, max_lon).map(Some)
+            }
+            (None, None, None, None) => Ok(None),
+            _ => Err(ErrorResponse::bad_request(
+                "either all of `minLat`, `minLon`, `maxLat`, `maxLon` or none expected",
+                "invalid_bounding_box",
+            )),
+        }
+    }
+
+    /// Build [HighlightOptions] out of this query's highlight/crop parameters, falling back to
+    /// the MeiliSearch-style defaults (`<em>`/`</em>` tags, `…` crop marker) when unset.
+    fn highlight_options(&self) -> HighlightOptions {
+        HighlightOptions {
+            pre_tag: self.highlightPreTag.clone().unwrap_or_else(|| "<em>".to_string()),
+            post_tag: self.highlightPostTag.clone().unwrap_or_else(|| "</em>".to_string()),
+            crop_length: self.cropLength,
+            crop_marker: self.cropMarker.clone().unwrap_or_else(|| "…".to_string()),
+        }
+    }
+
+    /// Parse this query's pagination parameters into a [Pagination], see [parse_pagination].
+    fn pagination(&self) -> HandlerResult<Pagination> {
+        parse_pagination(self.offset, self.limit, self.page, self.hitsPerPage)
+    }
+
+    /// Parse the comma-separated `facets` field into a validated [Facets].
+    fn facets(&self) -> HandlerResult<Facets> {
+        match &self.facets {
+            Some(facets) => Facets::new(facets.split(',').map(|facet| facet.trim().to_string()).collect()),
+            None => Ok(Facets::default()),
+        }
+    }
+
+    /// Parse `matchingStrategy`, defaulting to [MatchingStrategy::Last] when unset.
+    fn matching_strategy(&self) -> HandlerResult<MatchingStrategy> {
+        match &self.matchingStrategy {
+            Some(matching_strategy) => MatchingStrategy::parse(matching_strategy),
+            None => Ok(MatchingStrategy::default()),
+        }
+    }
 }
 
 /// The `/city/v1/search` endpoint. HTTP request: [`SearchQuery`], response: [`MultiCityResponse`].
 ///
-/// Returns list of cities matching the 'query' parameter.
-/// The response is limited to 10 cities and no pagination is provided.
+/// Returns a page of cities matching the `query` parameter, optionally narrowed to a viewport.
+/// Defaults to the first 10 hits; see `offset`/`limit`/`page`/`hitsPerPage` on [`SearchQuery`].
 #[api_v2_operation]
 pub(crate) async fn search(
     query: Query<SearchQuery>,
     app: Data<AppState>,
 ) -> JsonResult<MultiCityResponse> {
     let locations_es_repo = LocationsElasticRepository(app.get_ref());
-    let es_cities =
-        locations_es_repo.search(&query.query, query.language, query.countryIso.as_deref()).await?;
+    let bounding_box = query.bounding_box()?;
+    let highlight = query.highlight_options();
+    let pagination = query.pagination()?;
+    let facets = query.facets()?;
+    let matching_strategy = query.matching_strategy()?;
+    let (paged, facet_distribution) = locations_es_repo
+        .search(
+            &query.query,
+            query.language,
+            query.countryIso.as_deref(),
+            bounding_box,
+            &highlight,
+            pagination,
+            &facets,
+            matching_strategy,
+        )
+        .await?;
+
+    let cities = es_cities_into_city_responses(app.get_ref(), paged.items, query.language).await?;
+    Ok(Json(
+        MultiCityResponse::paginated(cities, paged.estimated_total_hits, paged.offset, paged.limit, paged.total_pages)
+            .with_facet_distribution(facet_distribution),
+    ))
+}
+
+/// Parse the mutually-exclusive `offset`+`limit` / `page`+`hitsPerPage` pagination parameters
+/// shared by [SearchQuery] and [FeaturedQuery] into a [Pagination], defaulting to `offset: 0,
+/// limit: 10` when none are given. Mirrors the MeiliSearch search API's pagination contract.
+fn parse_pagination(
+    offset: Option<u32>,
+    limit: Option<u32>,
+    page: Option<u32>,
+    hits_per_page: Option<u32>,
+) -> HandlerResult<Pagination> {
+    const DEFAULT_LIMIT: u32 = 10;
+
+    match (page, hits_per_page) {
+        (None, None) => Pagination::new(offset.unwrap_or(0), limit.unwrap_or(DEFAULT_LIMIT)),
+        _ if offset.is_some() || limit.is_some() => Err(ErrorResponse::bad_request(
+            "either `offset`/`limit` or `page`/`hitsPerPage` may be given, not both",
+            "invalid_pagination_params",
+        )),
+        _ => Pagination::from_page(page.unwrap_or(1), hits_per_page.unwrap_or(DEFAULT_LIMIT)),
+    }
+}
+
+/// Query for the `/city/v1/inBounds` endpoint.
+#[allow(non_snake_case)]
+#[derive(Apiv2Schema, Deserialize)]
+pub(crate) struct InBoundsQuery {
+    /// Southern edge latitude of the viewport, in decimal degrees.
+    minLat: f64,
+    /// Western edge longitude of the viewport, in decimal degrees.
+    minLon: f64,
+    /// Northern edge latitude of the viewport, in decimal degrees.
+    maxLat: f64,
+    /// Eastern edge longitude of the viewport, in decimal degrees.
+    maxLon: f64,
+    language: Language,
+}
+
+/// The `/city/v1/inBounds` endpoint. HTTP request: [`InBoundsQuery`], response:
+/// [`MultiCityResponse`].
+///
+/// Returns the cities whose centroid falls inside the given viewport, most populous first.
+/// At most 1000 cities are returned; cities beyond those are not included and no pagination is
+/// provided. Intended for map-driven clients that pan/zoom and only want the cities currently
+/// visible.
+#[api_v2_operation]
+pub(crate) async fn in_bounds(
+    query: Query<InBoundsQuery>,
+    app: Data<AppState>,
+) -> JsonResult<MultiCityResponse> {
+    let locations_es_repo = LocationsElasticRepository(app.get_ref());
+    let bbox = BoundingBox::new(query.minLat, query.minLon, query.maxLat, query.maxLon)?;
+    let es_cities = locations_es_repo.get_cities_in_bounds(bbox).await?;
 
     es_cities_into_resp(app.get_ref(), es_cities, query.language).await
 }
 
+/// Query for the `/city/v1/batch` endpoint.
+#[derive(Apiv2Schema, Deserialize)]
+pub(crate) struct BatchQuery {
+    /// Comma-separated list of city ids to fetch, e.g. `123,456`.
+    ids: String,
+    language: Language,
+}
+
+impl BatchQuery {
+    /// Parse the comma-separated `ids` field into individual city ids.
+    fn city_ids(&self) -> HandlerResult<Vec<u64>> {
+        self.ids
+            .split(',')
+            .map(|id| {
+                id.trim()
+                    .parse()
+                    .map_err(|_| ErrorResponse::bad_request(format!("invalid city id: '{}'", id), "invalid_city_id"))
+            })
+            .collect()
+    }
+}
+
+/// The `/city/v1/batch` endpoint. HTTP request: [`BatchQuery`], response: [`MultiCityResponse`].
+///
+/// Returns the cities for the given `ids`, in the same order, fetched (along with their regions)
+/// via a single Elasticsearch multi-get request each rather than one lookup per city.
+#[api_v2_operation]
+pub(crate) async fn batch(
+    query: Query<BatchQuery>,
+    app: Data<AppState>,
+) -> JsonResult<MultiCityResponse> {
+    let locations_es_repo = LocationsElasticRepository(app.get_ref());
+    let city_ids = query.city_ids()?;
+    let es_cities = locations_es_repo.get_cities(&city_ids).await?;
+
+    es_cities_into_resp(app.get_ref(), es_cities, query.language).await
+}
+
+/// Query for the `/city/v1/suggest` endpoint.
+#[allow(non_snake_case)]
+#[derive(Apiv2Schema, Deserialize)]
+pub(crate) struct SuggestQuery {
+    /// Short, possibly incomplete, as-you-type fragment of the city name.
+    fragment: String,
+    /// ISO 3166-1 alpha-2 country code. Can be used to limit scope of the suggestions to a given country.
+    countryIso: Option<String>,
+    language: Language,
+}
+
+/// Lightweight suggestion entity returned by `/city/v1/suggest`. Cheaper than [`CityResponse`] as
+/// it skips the region join done by [`ElasticCity::into_resp`].
+#[allow(non_snake_case)]
+#[derive(Apiv2Schema, Serialize)]
+pub(crate) struct CitySuggestion {
+    /// Id of the city, e.g. `123`.
+    id: u64,
+    /// E.g. `"Plzeň"`.
+    name: String,
+    /// ISO 3166-1 alpha-2 country code, or a custom 4-letter code, e.g. `"CZ"`.
+    countryIso: String,
+}
+
+/// A list of `CitySuggestion` API entities.
+#[derive(Apiv2Schema, Serialize)]
+pub(crate) struct SuggestResponse {
+    suggestions: Vec<CitySuggestion>,
+}
+
+/// The `/city/v1/suggest` endpoint. HTTP request: [`SuggestQuery`], response: [`SuggestResponse`].
+///
+/// Returns as-you-type completion suggestions matching the `fragment` parameter, backed by the
+/// Elasticsearch completion suggester. The response is limited to 10 suggestions and no
+/// pagination is provided.
+#[api_v2_operation]
+pub(crate) async fn suggest(
+    query: Query<SuggestQuery>,
+    app: Data<AppState>,
+) -> JsonResult<SuggestResponse> {
+    let locations_es_repo = LocationsElasticRepository(app.get_ref());
+    let es_suggestions = locations_es_repo
+        .suggest(&query.fragment, query.language, query.countryIso.as_deref())
+        .await?;
+
+    let suggestions: Vec<_> =
+        es_suggestions.into_iter().map(|it| it.into_resp(query.language)).collect::<HandlerResult<_>>()?;
+    Ok(Json(SuggestResponse { suggestions }))
+}
+
 /// Query for the `/city/v1/closest` endpoint.
+#[allow(non_snake_case)]
 #[derive(Apiv2Schema, Deserialize)]
 pub(crate) struct ClosestQuery {
     /// Latitude in decimal degrees with . as decimal separator.
@@ -122,6 +481,25 @@ pub(crate) struct ClosestQuery {
     /// Longitude in decimal degrees with . as decimal separator.
     lon: Option<f64>,
     language: Language,
+    /// Southern edge latitude of a rectangle to restrict candidates to. Mutually exclusive with
+    /// the other `boundary*` parameters.
+    boundaryMinLat: Option<f64>,
+    /// Western edge longitude of a rectangle to restrict candidates to.
+    boundaryMinLon: Option<f64>,
+    /// Northern edge latitude of a rectangle to restrict candidates to.
+    boundaryMaxLat: Option<f64>,
+    /// Eastern edge longitude of a rectangle to restrict candidates to.
+    boundaryMaxLon: Option<f64>,
+    /// Latitude of a circle's center to restrict candidates to. Mutually exclusive with the other
+    /// `boundary*` parameters.
+    boundaryCircleLat: Option<f64>,
+    /// Longitude of a circle's center to restrict candidates to.
+    boundaryCircleLon: Option<f64>,
+    /// Circle radius, in meters, to restrict candidates to.
+    boundaryRadius: Option<f64>,
+    /// ISO 3166-1 alpha-2 country code to restrict candidates to. Mutually exclusive with the
+    /// other `boundary*` parameters.
+    boundaryCountryIso: Option<String>,
 }
 
 impl ClosestQuery {
@@ -130,7 +508,51 @@ impl ClosestQuery {
         match (self.lat, self.lon) {
             (Some(lat), Some(lon)) => Ok(Some(Coordinates { lat, lon })),
             (None, None) => Ok(None),
-            _ => Err(BadRequest("either both or none of `lat`, `lon` expected".to_string())),
+            _ => Err(ErrorResponse::bad_request(
+                "either both or none of `lat`, `lon` expected",
+                "invalid_coordinates",
+            )),
+        }
+    }
+
+    /// Extract the optional spatial boundary out of query, error if a rect/circle is only
+    /// partially given or more than one boundary kind is given at once.
+    fn boundary(&self) -> HandlerResult<Option<Boundary>> {
+        let rect = match (self.boundaryMinLat, self.boundaryMinLon, self.boundaryMaxLat, self.boundaryMaxLon) {
+            (Some(min_lat), Some(min_lon), Some(max_lat), Some(max_lon)) => {
+                Some(Boundary::Rect(BoundingBox::new(min_lat, min_lon, max_lat, max_lon)?))
+            }
+            (None, None, None, None) => None,
+            _ => {
+                return Err(ErrorResponse::bad_request(
+                    "either all of `boundaryMinLat`, `boundaryMinLon`, `boundaryMaxLat`, `boundaryMaxLon` or none expected",
+                    "invalid_boundary",
+                ))
+            }
+        };
+
+        let circle = match (self.boundaryCircleLat, self.boundaryCircleLon, self.boundaryRadius) {
+            (Some(lat), Some(lon), Some(radius)) => Some(Boundary::circle(Coordinates { lat, lon }, radius)?),
+            (None, None, None) => None,
+            _ => {
+                return Err(ErrorResponse::bad_request(
+                    "either all of `boundaryCircleLat`, `boundaryCircleLon`, `boundaryRadius` or none expected",
+                    "invalid_boundary",
+                ))
+            }
+        };
+
+        let country = self.boundaryCountryIso.clone().map(Boundary::Country);
+
+        match (rect, circle, country) {
+            (Some(boundary), None, None) | (None, Some(boundary), None) | (None, None, Some(boundary)) => {
+                Ok(Some(boundary))
+            }
+            (None, None, None) => Ok(None),
+            _ => Err(ErrorResponse::bad_request(
+                "at most one of a rect, a circle or a country boundary expected",
+                "invalid_boundary",
+            )),
         }
     }
 }
@@ -146,12 +568,13 @@ pub(crate) async fn closest(
     app: Data<AppState>,
 ) -> JsonResult<CityResponse> {
     let locations_es_repo = LocationsElasticRepository(app.get_ref());
+    let boundary = query.boundary()?;
 
     let es_city = if let Some(coords) = query.coordinates()? {
         coords.validate()?; // validate explicitly, we don't want to validate when loading from ES.
-        locations_es_repo.get_city_by_coords(coords, None).await?
+        locations_es_repo.get_closest_city(coords, None, boundary).await?
     } else if let Some(coords) = get_request_fastly_geo_coords(request.headers()) {
-        locations_es_repo.get_city_by_coords(coords, Some(true)).await?
+        locations_es_repo.get_closest_city(coords, Some(true), boundary).await?
     } else {
         let city_id = match query.language {
             Language::CS => 101_748_113,   // Prague
@@ -163,7 +586,7 @@ pub(crate) async fn closest(
         locations_es_repo.get_city(city_id).await?
     };
 
-    Ok(Json(es_city.into_resp(app.get_ref(), query.language).await?))
+    Ok(Json(es_city.into_resp_single(app.get_ref(), query.language).await?))
 }
 
 /// Query for the `/city/v1/associatedFeatured` endpoint.
@@ -177,19 +600,36 @@ pub(crate) struct AssociatedFeaturedQuery {
 /// The `/city/v1/associatedFeatured` endpoint. HTTP request: [`AssociatedFeaturedQuery`],
 /// response: [`CityResponse`].
 ///
-/// For a given city id returns the closest featured city.
+/// For a given city id returns the closest featured city. Cached: honors `If-None-Match` and
+/// emits `Cache-Control`/`ETag` headers, see [CachedJson].
 #[api_v2_operation]
 pub(crate) async fn associated_featured(
+    request: HttpRequest,
     query: Query<AssociatedFeaturedQuery>,
     app: Data<AppState>,
-) -> JsonResult<CityResponse> {
-    let locations_es_repo = LocationsElasticRepository(app.get_ref());
-    let mut es_city = locations_es_repo.get_city(query.id).await?;
-    if !es_city.isFeatured {
-        es_city = locations_es_repo.get_closest_city(es_city.centroid, Some(true)).await?;
-    }
+) -> CachedJsonResult {
+    static CACHE: Lazy<ResponseCache<(u64, Language)>> = Lazy::new(ResponseCache::new);
+
+    let cached = CACHE
+        .get_or_compute((query.id, query.language), || async {
+            let locations_es_repo = LocationsElasticRepository(app.get_ref());
+            let mut es_city = locations_es_repo.get_city(query.id).await?;
+            if !es_city.isFeatured {
+                es_city = locations_es_repo.get_closest_city(es_city.centroid, Some(true), None).await?;
+            }
 
-    Ok(Json(es_city.into_resp(app.get_ref(), query.language).await?))
+            let resp = es_city.into_resp_single(app.get_ref(), query.language).await?;
+            serde_json::to_string(&resp).map_err(|e| InternalServerError(e.to_string()))
+        })
+        .await?;
+
+    let if_none_match = get_header_str(request.headers(), "If-None-Match");
+    Ok(CachedJson::new(cached, ttl_secs(), if_none_match))
+}
+
+/// Get the value of request header `name` as `&str`, or [None] if absent or not valid UTF-8.
+fn get_header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
 }
 
 /// Get [Coordinates] out of Fastly Geo headers or [None] if they are not set or are invalid.
@@ -205,18 +645,20 @@ fn get_request_fastly_geo_coords(headers: &HeaderMap) -> Option<Coordinates> {
 }
 
 impl ElasticCity {
-    /// Transform ElasticCity into CityResponse, fetching the region.
-    async fn into_resp<T: WithElastic>(
+    /// Transform ElasticCity into CityResponse, given a pre-fetched map of regions that must
+    /// contain this city's `regionId`.
+    fn into_resp(
         self,
-        app: &T,
+        regions: &HashMap<u64, ElasticRegion>,
         language: Language,
     ) -> HandlerResult<CityResponse> {
-        let locations_es_repo = LocationsElasticRepository(app);
-        let es_region = locations_es_repo.get_region(self.regionId).await?;
+        let region_id = self.regionId;
+        let es_region = regions
+            .get(&region_id)
+            .ok_or_else(|| InternalServerError(format!("Region#{} missing from batch fetch", region_id)))?;
 
-        let name_key = language.name_key();
-        let name = self.names.get(&name_key).ok_or_else(|| BadRequest(name_key.clone()))?;
-        let region_name = es_region.names.get(&name_key).ok_or_else(|| BadRequest(name_key))?;
+        let name = resolve_name(&self.names, language)?;
+        let region_name = resolve_name(&es_region.names, language)?;
 
         Ok(CityResponse {
             id: self.id,
@@ -224,20 +666,60 @@ impl ElasticCity {
             countryIso: self.countryIso,
             name: name.to_string(),
             regionName: region_name.to_string(),
+            highlightedName: self.highlightName,
         })
     }
+
+    /// Transform a single ElasticCity into CityResponse, fetching just its region. Convenience
+    /// wrapper around [ElasticCity::into_resp] for the single-city endpoints.
+    async fn into_resp_single<T: WithElastic>(
+        self,
+        app: &T,
+        language: Language,
+    ) -> HandlerResult<CityResponse> {
+        let locations_es_repo = LocationsElasticRepository(app);
+        let region_id = self.regionId;
+        let es_region = locations_es_repo.get_region(region_id).await?;
+
+        self.into_resp(&[(region_id, es_region)].into_iter().collect(), language)
+    }
+}
+
+impl ElasticCitySuggestion {
+    /// Transform into [CitySuggestion], resolving the localized name with fallback but without
+    /// fetching the region.
+    fn into_resp(self, language: Language) -> HandlerResult<CitySuggestion> {
+        let name = resolve_name(&self.names, language)?;
+
+        Ok(CitySuggestion { id: self.id, name: name.to_string(), countryIso: self.countryIso })
+    }
 }
 
-/// Convert a vector of [ElasticCity] into [MultiCityResponse], maintaining order and fetching
-/// required regions asynchronously all in parallel (which is somewhat redundant with
-/// [ElasticRegion] cache).
+/// Convert a vector of [ElasticCity] into a vector of [CityResponse], maintaining order. Fetches
+/// the distinct regions referenced by `es_cities` with a single Elasticsearch multi-get request,
+/// rather than one lookup per city.
+async fn es_cities_into_city_responses<T: WithElastic>(
+    app: &T,
+    es_cities: Vec<ElasticCity>,
+    language: Language,
+) -> HandlerResult<Vec<CityResponse>> {
+    let locations_es_repo = LocationsElasticRepository(app);
+
+    let mut region_ids: Vec<u64> = es_cities.iter().map(|city| city.regionId).collect();
+    region_ids.sort_unstable();
+    region_ids.dedup();
+    let regions = locations_es_repo.get_regions(&region_ids).await?;
+
+    es_cities.into_iter().map(|it| it.into_resp(&regions, language)).collect()
+}
+
+/// Convert a vector of [ElasticCity] into [MultiCityResponse]. See
+/// [es_cities_into_city_responses].
 async fn es_cities_into_resp<T: WithElastic>(
     app: &T,
     es_cities: Vec<ElasticCity>,
     language: Language,
 ) -> JsonResult<MultiCityResponse> {
-    let city_futures: FuturesOrdered<_> =
-        es_cities.into_iter().map(|it| it.into_resp(app, language)).collect();
-
-    city_futures.try_collect().await.map(|cities| Json(MultiCityResponse { cities }))
+    let cities = es_cities_into_city_responses(app, es_cities, language).await?;
+    Ok(Json(MultiCityResponse::unpaginated(cities)))
 }
@@ -3,5 +3,5 @@
 use crate::{response::ErrorResponse, Request};
 
 pub(crate) async fn not_found(req: Request) -> ErrorResponse {
-    ErrorResponse::NotFound(format!("Resource {} does not exist.", req.uri()))
+    ErrorResponse::not_found(format!("Resource {} does not exist.", req.uri()), "resource_not_found")
 }
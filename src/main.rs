@@ -31,6 +31,7 @@ use tokio::runtime::{self, Runtime};
 mod handlers {
     pub(crate) mod city;
 }
+mod fairings;
 mod response;
 /// Module for stateless services (that may depend on stateful ones from [stateful] module).
 mod services {
@@ -38,6 +39,7 @@ mod services {
 }
 /// Module for "stateful" services - those that need initialisation on startup and a living state.
 mod stateful {
+    pub(crate) mod cache;
     pub(crate) mod elasticsearch;
 }
 
@@ -52,6 +54,7 @@ fn main() {
 
     rocket::ignite()
         .manage(app_state)
+        .attach(fairings::ApiPolicyFairing)
         .register(catchers![response::not_found, response::internal_server_error])
         .mount(
             "/",
@@ -59,6 +62,9 @@ fn main() {
                 handlers::city::get,
                 handlers::city::featured,
                 handlers::city::search,
+                handlers::city::suggest,
+                handlers::city::in_bounds,
+                handlers::city::batch,
                 handlers::city::closest,
                 handlers::city::associated_featured,
             ],
@@ -1,15 +1,17 @@
 //! OK and error response types to be used by endpoints.
 
+use crate::stateful::cache::CachedResponse;
 use rocket::{
     catch,
-    http::Status,
+    http::{ContentType, Status},
     request::FormParseError,
     response,
     response::{status::Custom, Responder},
-    Request,
+    Request, Response,
 };
 use rocket_contrib::json::Json;
 use serde::Serialize;
+use std::io::Cursor;
 use validator::ValidationErrors;
 
 /// Convenience alias for [Result] whose error is [ErrorResponse], to be used by supportive code.
@@ -18,35 +20,95 @@ pub(crate) type HandlerResult<T> = Result<T, ErrorResponse>;
 /// Result type to be used by endpoints. Either OK [Json] or error [ErrorResponse].
 pub(crate) type JsonResult<T> = HandlerResult<Json<T>>;
 
-/// Possible error endpoint responses.
+/// Result type for read-heavy, cacheable endpoints. Either OK [CachedJson] or error
+/// [ErrorResponse].
+pub(crate) type CachedJsonResult = HandlerResult<CachedJson>;
+
+/// A pre-serialized JSON response body decorated with `Cache-Control: max-age=...` and `ETag`
+/// headers, resolving to a bare HTTP 304 Not Modified when the caller's `If-None-Match` matches.
+pub(crate) struct CachedJson {
+    cached: CachedResponse,
+    max_age_secs: u64,
+    not_modified: bool,
+}
+
+impl CachedJson {
+    /// Build a cacheable response out of a precomputed `cached` entry, deciding whether the
+    /// caller's `if_none_match` header value means we can short-circuit to a 304.
+    pub(crate) fn new(cached: CachedResponse, max_age_secs: u64, if_none_match: Option<&str>) -> Self {
+        let not_modified = if_none_match == Some(cached.etag.as_str());
+        Self { cached, max_age_secs, not_modified }
+    }
+}
+
+impl<'r> Responder<'r> for CachedJson {
+    fn respond_to(self, _req: &Request<'_>) -> response::Result<'r> {
+        let mut response = if self.not_modified {
+            Response::build().status(Status::new(304, "Not Modified")).finalize()
+        } else {
+            Response::build()
+                .header(ContentType::JSON)
+                .sized_body(Cursor::new(self.cached.body.into_bytes()))
+                .finalize()
+        };
+        response.set_raw_header("Cache-Control", format!("max-age={}", self.max_age_secs));
+        response.set_raw_header("ETag", self.cached.etag);
+        Ok(response)
+    }
+}
+
+/// Possible error endpoint responses. Serialized as a structured `{message, code, type, link}`
+/// body, see [ErrorResponse::respond_to], so clients can programmatically distinguish failures
+/// instead of pattern-matching on `message`.
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum ErrorResponse {
-    /// HTTP 400 Bad Request: client sent something wrong.
-    #[error("Bad Request: {0}")]
-    BadRequest(String),
-    /// HTTP 404 Not Found: this path or entity does not exist.
-    #[error("Not Found: {0}")]
-    NotFound(String),
+    /// HTTP 400 Bad Request: client sent something wrong. `code` is a stable, machine-readable
+    /// identifier for the specific problem, e.g. `"invalid_lat"` or `"unknown_parameter"`.
+    #[error("Bad Request: {message}")]
+    BadRequest { message: String, code: String },
+    /// HTTP 404 Not Found: this path or entity does not exist. `code` identifies what kind of
+    /// resource was missing, e.g. `"city_not_found"`.
+    #[error("Not Found: {message}")]
+    NotFound { message: String, code: String },
     /// HTTP 500 Internal Server Error: something went real wrong on the server.
     #[error("Internal Server Error: {0}")]
     InternalServerError(String),
 }
 
+impl ErrorResponse {
+    /// Convenience constructor for [ErrorResponse::BadRequest].
+    pub(crate) fn bad_request(message: impl Into<String>, code: &str) -> Self {
+        Self::BadRequest { message: message.into(), code: code.to_string() }
+    }
+
+    /// Convenience constructor for [ErrorResponse::NotFound].
+    pub(crate) fn not_found(message: impl Into<String>, code: &str) -> Self {
+        Self::NotFound { message: message.into(), code: code.to_string() }
+    }
+}
+
 /// Make Rocket understand our error responses.
 impl<'r> Responder<'r> for ErrorResponse {
     fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
-        let http_status = match self {
-            Self::BadRequest(_) => Status::BadRequest,
-            Self::NotFound(_) => Status::NotFound,
-            Self::InternalServerError(_) => Status::InternalServerError,
-        };
-
         #[derive(Serialize)]
         struct ErrorPayload {
             message: String,
+            code: String,
+            #[serde(rename = "type")]
+            error_type: &'static str,
+            /// Link to documentation about this error `code`. `None` until such docs exist.
+            link: Option<String>,
         }
 
-        let payload = ErrorPayload { message: self.to_string() };
+        let (http_status, error_type, code, message) = match self {
+            Self::BadRequest { message, code } => (Status::BadRequest, "invalid_request", code, message),
+            Self::NotFound { message, code } => (Status::NotFound, "invalid_request", code, message),
+            Self::InternalServerError(message) => {
+                (Status::InternalServerError, "internal", "internal_error".to_string(), message)
+            }
+        };
+
+        let payload = ErrorPayload { message, code, error_type, link: None };
         let response = Custom(http_status, Json(payload));
         response.respond_to(req)
     }
@@ -59,22 +121,45 @@ impl From<elasticsearch::Error> for ErrorResponse {
     }
 }
 
-/// Convert from [validator] errors into bad requests.
+/// Convert from [validator] errors into bad requests, picking the first offending field so the
+/// response names it and its received value, rather than the previous flattened `to_string()`.
 impl From<ValidationErrors> for ErrorResponse {
     fn from(err: ValidationErrors) -> Self {
-        Self::BadRequest(err.to_string())
+        // `field_errors()` returns a HashMap with unspecified iteration order; sort by field name
+        // so the response is deterministic when multiple fields fail validation at once.
+        let mut field_errors: Vec<_> = err.field_errors().into_iter().collect();
+        field_errors.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        match field_errors.into_iter().next() {
+            Some((field, errors)) => match errors.first() {
+                Some(error) => Self::bad_request(describe_field_error(field, error), &format!("invalid_{}", field)),
+                None => Self::bad_request(err.to_string(), "invalid_request"),
+            },
+            None => Self::bad_request(err.to_string(), "invalid_request"),
+        }
+    }
+}
+
+/// Describe a single [validator::ValidationError] for `field`, naming the field and (when known)
+/// the value it received, e.g. `` `lat` must be between -90.0 and 90.0, got 123.0 ``.
+fn describe_field_error(field: &str, error: &validator::ValidationError) -> String {
+    match (error.params.get("min"), error.params.get("max"), error.params.get("value")) {
+        (Some(min), Some(max), Some(value)) => {
+            format!("`{}` must be between {} and {}, got {}", field, min, max, value)
+        }
+        _ => format!("`{}` is invalid", field),
     }
 }
 
 impl<'f> From<FormParseError<'f>> for ErrorResponse {
     fn from(err: FormParseError<'f>) -> Self {
-        Self::BadRequest(format!("{:?}", err))
+        Self::bad_request(format!("{:?}", err), "unknown_parameter")
     }
 }
 
 #[catch(404)]
 pub(crate) fn not_found(req: &Request<'_>) -> ErrorResponse {
-    ErrorResponse::NotFound(req.uri().to_string())
+    ErrorResponse::not_found(req.uri().to_string(), "resource_not_found")
 }
 
 #[catch(500)]
@@ -1,16 +1,13 @@
 //! Stateless Locations repository backed by Elasticsearch.
 
 use crate::{
-    response::{
-        ErrorResponse::{InternalServerError, NotFound},
-        HandlerResult,
-    },
+    response::{ErrorResponse, ErrorResponse::InternalServerError, HandlerResult},
     stateful::elasticsearch::WithElastic,
 };
 use actix_web::http::StatusCode;
 use dashmap::DashMap;
 use elasticsearch::{
-    http::response::Response as EsResponse, Error as EsError, GetParts::IndexTypeId,
+    http::response::Response as EsResponse, Error as EsError, GetParts::IndexTypeId, MgetParts,
     SearchParts::Index,
 };
 use log::{debug, error};
@@ -25,9 +22,16 @@ use validator_derive::Validate; // redundant use due to https://github.com/Keats
 const REGION_INDEX: &str = "region";
 const CITY_INDEX: &str = "city";
 const EXCLUDED_FIELDS: &[&str] = &["centroid", "geometry", "population"];
+const SUGGEST_NAME: &str = "city-suggest";
+const SUGGEST_RESULT_LIMIT: i64 = 10;
+
+/// Cache of [ElasticRegion]s keyed by id, shared by [LocationsElasticRepository::get_region] and
+/// [LocationsElasticRepository::get_regions] so that batched and single-region lookups reuse the
+/// same entries.
+static REGION_CACHE: Lazy<DashMap<u64, ElasticRegion>> = Lazy::new(DashMap::new);
 
 /// Language for response localization. Serialized as two-letter ISO 639-1 lowercase language code.
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum Language {
     CS,
@@ -38,9 +42,51 @@ pub(crate) enum Language {
 }
 
 impl Language {
-    pub(crate) fn name_key(self) -> String {
+    pub(crate) fn name_key(self) -> NameKey {
         format!("name.{:?}", self).to_lowercase()
     }
+
+    /// Ordered, non-empty chain of [NameKey]s to try when resolving a localized name for this
+    /// language, most specific first, always ending in [ROOT_NAME_KEY].
+    ///
+    /// Modeled on ICU-style locale fallback: a requested language degrades through progressively
+    /// more general (but more likely to be populated) locales before giving up entirely.
+    pub(crate) fn fallback_keys(self) -> Vec<NameKey> {
+        let mut keys = match self {
+            Self::CS => vec![Self::CS.name_key(), Self::EN.name_key()],
+            Self::DE => vec![Self::DE.name_key(), Self::EN.name_key()],
+            Self::EN => vec![Self::EN.name_key()],
+            Self::PL => vec![Self::PL.name_key(), Self::EN.name_key()],
+            Self::SK => vec![Self::SK.name_key(), Self::CS.name_key(), Self::EN.name_key()],
+        };
+        keys.push(ROOT_NAME_KEY.to_owned());
+        keys.dedup();
+        keys
+    }
+}
+
+/// Key of a localized name field of a [ElasticCity] or [ElasticRegion], e.g. `"name.en"`.
+pub(crate) type NameKey = String;
+
+/// Name key assumed to always be populated in Elasticsearch, the final link of every
+/// [Language::fallback_keys] chain.
+const ROOT_NAME_KEY: &str = "name.root";
+
+/// Resolve the localized name for `language` out of `names`, degrading gracefully through
+/// [Language::fallback_keys] when the most specific name is missing.
+///
+/// Only fails with [ErrorResponse::BadRequest] if the entire fallback chain (including the root key) is
+/// exhausted, which should not happen in practice as Elasticsearch data always carries the root
+/// name.
+pub(crate) fn resolve_name(names: &HashMap<String, String>, language: Language) -> HandlerResult<&str> {
+    language
+        .fallback_keys()
+        .into_iter()
+        .find_map(|key| names.get(&key))
+        .map(String::as_str)
+        .ok_or_else(|| {
+            ErrorResponse::bad_request(format!("No name found for `{}`.", language.name_key()), "missing_field")
+        })
 }
 
 /// Simple structure to represent a geo point, with latitude and longitude in decimal degrees.
@@ -59,6 +105,294 @@ impl Coordinates {
     }
 }
 
+/// Geographic bounding box (viewport), in decimal degrees.
+#[allow(non_snake_case)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Validate)]
+pub(crate) struct BoundingBox {
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub(crate) minLat: f64,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub(crate) minLon: f64,
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub(crate) maxLat: f64,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub(crate) maxLon: f64,
+}
+
+impl BoundingBox {
+    /// Construct a [BoundingBox], validating lat/lon ranges and that it is not inverted
+    /// (`minLat` <= `maxLat`).
+    pub(crate) fn new(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> HandlerResult<Self> {
+        let bbox = Self { minLat: min_lat, minLon: min_lon, maxLat: max_lat, maxLon: max_lon };
+        bbox.validate()?;
+        if bbox.minLat > bbox.maxLat {
+            return Err(ErrorResponse::bad_request(
+                "`minLat` must not be greater than `maxLat`",
+                "invalid_bounding_box",
+            ));
+        }
+
+        Ok(bbox)
+    }
+
+    /// Elasticsearch `geo_bounding_box` query representation of this box, as [serde_json::Value].
+    fn es_bounding_box(self) -> JsonValue {
+        json!({
+            "top_left": { "lat": self.maxLat, "lon": self.minLon },
+            "bottom_right": { "lat": self.minLat, "lon": self.maxLon },
+        })
+    }
+}
+
+/// Spatial constraint restricting candidate cities to a region, before the distance sort in
+/// [LocationsElasticRepository::get_closest_city]. Modelled after the Pelias geocoding API's
+/// `boundary.rect` / `boundary.circle` / `boundary.country` reverse-geocoding filters.
+#[derive(Clone, Debug)]
+pub(crate) enum Boundary {
+    /// Restrict to a rectangle.
+    Rect(BoundingBox),
+    /// Restrict to a circle given by its `center` and `radius_meters`.
+    Circle { center: Coordinates, radius_meters: f64 },
+    /// Restrict to a single country, by its ISO code.
+    Country(String),
+}
+
+impl Boundary {
+    /// Construct a [Boundary::Circle], validating that `radius_meters` is positive.
+    pub(crate) fn circle(center: Coordinates, radius_meters: f64) -> HandlerResult<Self> {
+        if radius_meters <= 0.0 {
+            return Err(ErrorResponse::bad_request("`radius` must be positive", "invalid_radius"));
+        }
+
+        Ok(Self::Circle { center, radius_meters })
+    }
+
+    /// Elasticsearch filter clause restricting the `centroid` field to this boundary, as
+    /// [serde_json::Value], to be pushed onto a `bool.filter` array.
+    fn es_filter(&self) -> JsonValue {
+        match self {
+            Self::Rect(bounding_box) => json!({ "geo_bounding_box": { "centroid": bounding_box.es_bounding_box() } }),
+            Self::Circle { center, radius_meters } => json!({
+                "geo_distance": {
+                    "distance": format!("{}m", radius_meters),
+                    "centroid": center,
+                }
+            }),
+            Self::Country(country_iso) => json!({ "term": { "countryIso": country_iso } }),
+        }
+    }
+}
+
+/// Options controlling how the matched part of a searched-for name is highlighted, imported from
+/// the MeiliSearch search API's highlight/crop parameters.
+#[derive(Clone, Debug)]
+pub(crate) struct HighlightOptions {
+    pub(crate) pre_tag: String,
+    pub(crate) post_tag: String,
+    pub(crate) crop_length: Option<u32>,
+    pub(crate) crop_marker: String,
+}
+
+impl HighlightOptions {
+    /// Elasticsearch `highlight` clause requesting fragments of `field`, as [serde_json::Value].
+    fn es_highlight(&self, field: &str) -> JsonValue {
+        json!({
+            "pre_tags": [self.pre_tag],
+            "post_tags": [self.post_tag],
+            "fields": {
+                field: {
+                    "number_of_fragments": 1,
+                    "fragment_size": self.crop_length.unwrap_or(DEFAULT_CROP_LENGTH),
+                }
+            },
+        })
+    }
+
+    /// Crop marker appended to `fragment` when it looks like it was truncated to `crop_length`.
+    ///
+    /// `fragment_size` in [Self::es_highlight] is measured by Elasticsearch against the untagged
+    /// text, so the tags themselves must be stripped back out before comparing against `limit` —
+    /// otherwise every hit whose tagged length merely happens to reach `limit` (because of the
+    /// tags, not actual truncation) gets a spurious marker appended.
+    fn apply_crop_marker(&self, fragment: String) -> String {
+        let limit = self.crop_length.unwrap_or(DEFAULT_CROP_LENGTH);
+        let untagged_len = fragment.replace(&self.pre_tag, "").replace(&self.post_tag, "").chars().count() as u32;
+        if untagged_len >= limit {
+            format!("{}{}", fragment, self.crop_marker)
+        } else {
+            fragment
+        }
+    }
+}
+
+const DEFAULT_CROP_LENGTH: u32 = 100;
+
+/// Maximum allowed `limit`/`hitsPerPage`, bounding how much a single request can pull out of
+/// Elasticsearch in one page.
+const MAX_PAGE_LIMIT: u32 = 200;
+
+/// Normalized offset/limit pagination for search-like endpoints, translating either
+/// `offset`+`limit` or `page`+`hits_per_page` MeiliSearch-style parameters into an Elasticsearch
+/// `from`/`size` pair.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Pagination {
+    offset: u32,
+    limit: u32,
+    /// Set when constructed via [Pagination::from_page], so [PagedResult::new] can compute
+    /// `total_pages`.
+    page: Option<u32>,
+}
+
+impl Pagination {
+    /// Construct from an `offset`+`limit` pair, validating `limit` against [MAX_PAGE_LIMIT].
+    pub(crate) fn new(offset: u32, limit: u32) -> HandlerResult<Self> {
+        Self::validate_limit(limit)?;
+        Ok(Self { offset, limit, page: None })
+    }
+
+    /// Construct from a 1-based `page`+`hits_per_page` pair, validating `hits_per_page` against
+    /// [MAX_PAGE_LIMIT].
+    pub(crate) fn from_page(page: u32, hits_per_page: u32) -> HandlerResult<Self> {
+        Self::validate_limit(hits_per_page)?;
+        let page = page.max(1);
+        let offset = (page - 1).checked_mul(hits_per_page).ok_or_else(|| {
+            ErrorResponse::bad_request("`page` is too large for the given `hitsPerPage`", "invalid_page")
+        })?;
+        Ok(Self { offset, limit: hits_per_page, page: Some(page) })
+    }
+
+    fn validate_limit(limit: u32) -> HandlerResult<()> {
+        if limit > MAX_PAGE_LIMIT {
+            return Err(ErrorResponse::bad_request(
+                format!("`limit`/`hitsPerPage` must not exceed {}", MAX_PAGE_LIMIT),
+                "invalid_limit",
+            ));
+        }
+        Ok(())
+    }
+
+    fn es_from(self) -> i64 {
+        self.offset as i64
+    }
+
+    fn es_size(self) -> i64 {
+        self.limit as i64
+    }
+}
+
+/// A page of `T` results alongside pagination metadata, mirroring the MeiliSearch search API's
+/// pagination response contract.
+#[derive(Debug)]
+pub(crate) struct PagedResult<T> {
+    pub(crate) items: Vec<T>,
+    pub(crate) estimated_total_hits: u64,
+    pub(crate) offset: u32,
+    pub(crate) limit: u32,
+    /// Only set when the request used `page`/`hitsPerPage` pagination.
+    pub(crate) total_pages: Option<u32>,
+}
+
+impl<T> PagedResult<T> {
+    fn new(items: Vec<T>, estimated_total_hits: u64, pagination: Pagination) -> Self {
+        let total_pages = pagination.page.map(|_| {
+            let limit = u64::from(pagination.limit).max(1);
+            ((estimated_total_hits + limit - 1) / limit) as u32
+        });
+
+        Self { items, estimated_total_hits, offset: pagination.offset, limit: pagination.limit, total_pages }
+    }
+}
+
+/// Maximum number of facet fields that may be requested in a single
+/// [LocationsElasticRepository::search] call.
+const MAX_FACET_FIELDS: usize = 4;
+
+/// Maximum number of distinct values returned per requested facet field.
+const FACET_BUCKET_LIMIT: u32 = 50;
+
+/// Elasticsearch field backing a given facet name, or `None` if `facet` is not facetable.
+fn facet_es_field(facet: &str) -> Option<&'static str> {
+    match facet {
+        "countryIso" => Some("countryIso"),
+        "regionId" => Some("regionId"),
+        _ => None,
+    }
+}
+
+/// Validated set of fields to return facet counts for, alongside [LocationsElasticRepository::search]
+/// hits, mirroring the MeiliSearch search API's `facets` parameter.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Facets(Vec<String>);
+
+impl Facets {
+    /// Validate `fields` against the known facetable fields and [MAX_FACET_FIELDS].
+    pub(crate) fn new(fields: Vec<String>) -> HandlerResult<Self> {
+        if fields.len() > MAX_FACET_FIELDS {
+            return Err(ErrorResponse::bad_request(
+                format!("At most {} facet fields may be requested", MAX_FACET_FIELDS),
+                "too_many_facets",
+            ));
+        }
+        if let Some(unknown) = fields.iter().find(|field| facet_es_field(field).is_none()) {
+            return Err(ErrorResponse::bad_request(format!("Unknown facet `{}`", unknown), "invalid_facet"));
+        }
+
+        Ok(Self(fields))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Elasticsearch `aggs` clause requesting a `terms` aggregation per requested field, as
+    /// [serde_json::Value].
+    fn es_aggs(&self) -> JsonValue {
+        let aggs: serde_json::Map<String, JsonValue> = self
+            .0
+            .iter()
+            .map(|field| {
+                let es_field = facet_es_field(field).expect("facet fields are validated in Facets::new");
+                (field.clone(), json!({ "terms": { "field": es_field, "size": FACET_BUCKET_LIMIT } }))
+            })
+            .collect();
+
+        json!(aggs)
+    }
+}
+
+/// Controls how strictly every term of a [LocationsElasticRepository::search] `query` must match,
+/// adapted from the MeiliSearch search API's `matchingStrategy` parameter.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum MatchingStrategy {
+    /// Every query term must match (`operator: "and"`, `minimum_should_match: "100%"`).
+    All,
+    /// Best-effort matching: Elasticsearch's own `bool_prefix` default, where a hit needs to match
+    /// any one query term and hits matching more terms score higher. No extra query clause and
+    /// no extra round trip are used. Unlike MeiliSearch's `last`, trailing terms are not dropped
+    /// one at a time; all terms are matched optionally in a single query.
+    Last,
+}
+
+impl Default for MatchingStrategy {
+    fn default() -> Self {
+        Self::Last
+    }
+}
+
+impl MatchingStrategy {
+    /// Parse a `matchingStrategy` query parameter value (`"all"` or `"last"`).
+    pub(crate) fn parse(value: &str) -> HandlerResult<Self> {
+        match value {
+            "all" => Ok(Self::All),
+            "last" => Ok(Self::Last),
+            _ => Err(ErrorResponse::bad_request(
+                format!("Unknown matching strategy `{}`, expected `all` or `last`", value),
+                "invalid_matching_strategy",
+            )),
+        }
+    }
+}
+
 /// Repository of Elastic City, Region Locations entities. Thin wrapper around app state.
 pub(crate) struct LocationsElasticRepository<'a, S: WithElastic>(pub(crate) &'a S);
 
@@ -69,88 +403,143 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
         self.get_entity(id, CITY_INDEX, "City").await
     }
 
+    /// Get multiple [ElasticCity]s from Elasticsearch given their `ids`, in a single multi-get
+    /// round trip. Preserves the order of `ids` (including repeats); ids with no matching
+    /// document are skipped.
+    pub(crate) async fn get_cities(&self, ids: &[u64]) -> HandlerResult<Vec<ElasticCity>> {
+        let cities: HashMap<u64, ElasticCity> = self.mget_entities(ids, CITY_INDEX).await?;
+        Ok(ids.iter().filter_map(|id| cities.get(id).cloned()).collect())
+    }
+
     /// Get [ElasticRegion] from Elasticsearch given its `id`. Async.
     pub(crate) async fn get_region(&self, id: u64) -> HandlerResult<ElasticRegion> {
-        static CACHE: Lazy<DashMap<u64, ElasticRegion>> = Lazy::new(DashMap::new);
+        let mut regions = self.get_regions(&[id]).await?;
+        regions
+            .remove(&id)
+            .ok_or_else(|| ErrorResponse::not_found(format!("Region#{} not found.", id), "region_not_found"))
+    }
+
+    /// Get multiple [ElasticRegion]s from Elasticsearch given their `ids`, fetching any not
+    /// already present in [REGION_CACHE] with a single multi-get round trip.
+    pub(crate) async fn get_regions(&self, ids: &[u64]) -> HandlerResult<HashMap<u64, ElasticRegion>> {
+        let mut regions = HashMap::with_capacity(ids.len());
+        let mut missing_ids = Vec::new();
+        for &id in ids {
+            match REGION_CACHE.get(&id) {
+                Some(region) => {
+                    regions.insert(id, region.value().clone());
+                }
+                None => missing_ids.push(id),
+            }
+        }
 
-        if let Some(record) = CACHE.get(&id) {
-            return Ok(record.value().clone());
+        if !missing_ids.is_empty() {
+            let fetched: HashMap<u64, ElasticRegion> =
+                self.mget_entities(&missing_ids, REGION_INDEX).await?;
+            for (id, region) in fetched {
+                REGION_CACHE.insert(id, region.clone());
+                regions.insert(id, region);
+            }
         }
 
-        let entity: ElasticRegion = self.get_entity(id, REGION_INDEX, "Region").await?;
-        CACHE.insert(id, entity.clone());
-        Ok(entity)
+        Ok(regions)
     }
 
-    /// Get a list of featured cities. Async.
-    pub(crate) async fn get_featured_cities(&self) -> HandlerResult<Vec<ElasticCity>> {
-        self.search_city(
-            json!({
-                "query": {
-                    "term": {
-                        "isFeatured": true,
-                    }
-                },
-                "sort": [
-                    "countryIso",
-                    { "population": "desc" },
-                ],
-            }),
-            1000,
-        )
-        .await
+    /// Get a page of featured cities, ordered by country and then by population. Async.
+    pub(crate) async fn get_featured_cities(
+        &self,
+        pagination: Pagination,
+    ) -> HandlerResult<PagedResult<ElasticCity>> {
+        let (cities, total_hits, _facet_counts) = self
+            .search_city(
+                json!({
+                    "query": {
+                        "term": {
+                            "isFeatured": true,
+                        }
+                    },
+                    "sort": [
+                        "countryIso",
+                        { "population": "desc" },
+                    ],
+                }),
+                pagination.es_from(),
+                pagination.es_size(),
+                None,
+                &Facets::default(),
+            )
+            .await?;
+
+        Ok(PagedResult::new(cities, total_hits, pagination))
     }
 
-    /// Search for cities. Optionally limit to a country given its ISO code.
+    /// Search for cities. Optionally limit to a country given its ISO code and/or a geographic
+    /// `bounding_box` viewport. `highlight` controls the highlighted snippet returned for the
+    /// matched name, see [HighlightOptions]; `pagination` controls the returned page of results;
+    /// `facets` requests per-field hit counts alongside the page, see [Facets]; `matching_strategy`
+    /// controls how strictly every term of `query` must match, see [MatchingStrategy]. Returns
+    /// those facet counts keyed by facet field name and then by facet value.
     pub(crate) async fn search(
         &self,
         query: &str,
         language: Language,
         country_iso: Option<&str>,
-    ) -> HandlerResult<Vec<ElasticCity>> {
+        bounding_box: Option<BoundingBox>,
+        highlight: &HighlightOptions,
+        pagination: Pagination,
+        facets: &Facets,
+        matching_strategy: MatchingStrategy,
+    ) -> HandlerResult<(PagedResult<ElasticCity>, HashMap<String, HashMap<String, u64>>)> {
         let name_key = language.name_key();
+        let highlight_field = format!("{}.autocomplete", name_key);
 
-        self.search_city(
+        let mut filters: Vec<JsonValue> = Vec::new();
+        if let Some(iso_code) = country_iso {
+            filters.push(json!({ "term": { "countryIso": iso_code } }));
+        }
+        if let Some(bbox) = bounding_box {
+            filters.push(json!({ "geo_bounding_box": { "centroid": bbox.es_bounding_box() } }));
+        }
+
+        let mut multi_match = json!({
+            "query": query,
+            "fields": [
+                // Match against the specified language with diacritics.
+                // Use the highest boost (8) because these three fields are most specific.
+                format!("{}.autocomplete^8.0", name_key),
+                format!("{}.autocomplete._2gram^8.0", name_key),
+                format!("{}.autocomplete._3gram^8.0", name_key),
+                // Match against ascii versions of the name to match queries without diacritics.
+                // Lower boost by factor of two, to prefer cities that matched with diacritics.
+                format!("{}.autocomplete_ascii^4.0", name_key),
+                format!("{}.autocomplete_ascii._2gram^4.0", name_key),
+                format!("{}.autocomplete_ascii._3gram^4.0", name_key),
+                // Match against all language mutations with diacritics.
+                // Lower the boost by factor of 4 to prefer matches in specified language.
+                "name.all.autocomplete^2.0",
+                "name.all.autocomplete._2gram^2.0",
+                "name.all.autocomplete._3gram^2.0",
+                // Match against ascii version of all language mutations.
+                // Lower the boost by factor of 8 because this is the least specific field.
+                "name.all.autocomplete_ascii^1.0",
+                "name.all.autocomplete_ascii._2gram^1.0",
+                "name.all.autocomplete_ascii._3gram^1.0",
+            ],
+            "type": "bool_prefix",
+        });
+        if matching_strategy == MatchingStrategy::All {
+            multi_match["operator"] = json!("and");
+            multi_match["minimum_should_match"] = json!("100%");
+        }
+
+        let (cities, total_hits, facet_counts) = self.search_city(
             json!({
                 "query": {
                     "function_score": {
                         "query": {
                             "bool": {
-                                "must": [{
-                                    "multi_match": {
-                                        "query": query,
-                                        "fields": [
-                                            // Match against the specified language with diacritics.
-                                            // Use the highest boost (8) because these three fields are most specific.
-                                            format!("{}.autocomplete^8.0", name_key),
-                                            format!("{}.autocomplete._2gram^8.0", name_key),
-                                            format!("{}.autocomplete._3gram^8.0", name_key),
-                                            // Match against ascii versions of the name to match queries without diacritics.
-                                            // Lower boost by factor of two, to prefer cities that matched with diacritics.
-                                            format!("{}.autocomplete_ascii^4.0", name_key),
-                                            format!("{}.autocomplete_ascii._2gram^4.0", name_key),
-                                            format!("{}.autocomplete_ascii._3gram^4.0", name_key),
-                                            // Match against all language mutations with diacritics.
-                                            // Lower the boost by factor of 4 to prefer matches in specified language.
-                                            "name.all.autocomplete^2.0",
-                                            "name.all.autocomplete._2gram^2.0",
-                                            "name.all.autocomplete._3gram^2.0",
-                                            // Match against ascii version of all language mutations.
-                                            // Lower the boost by factor of 8 because this is the least specific field.
-                                            "name.all.autocomplete_ascii^1.0",
-                                            "name.all.autocomplete_ascii._2gram^1.0",
-                                            "name.all.autocomplete_ascii._3gram^1.0",
-                                        ],
-                                        "type": "bool_prefix",
-                                    }
-                                }],
-                                "filter": match country_iso {
-                                    Some(iso_code) => json!([{
-                                        "term": {
-                                            "countryIso": iso_code
-                                        }}]),
-                                    None => json!([])
-                                },
+                                "must": [{ "multi_match": multi_match }],
+                                "filter": filters,
                             }
                         },
                         // Boost cities with higher population.
@@ -167,18 +556,104 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
                         }],
                     }
                 },
+                "highlight": highlight.es_highlight(&highlight_field),
             }),
-            10,
+            pagination.es_from(),
+            pagination.es_size(),
+            Some((highlight_field.as_str(), highlight)),
+            facets,
         )
-        .await
+        .await?;
+
+        Ok((PagedResult::new(cities, total_hits, pagination), facet_counts))
     }
 
-    /// Get a city closest to given geo `coords`, optionally filter by `is_featured`.
+    /// Get the cities whose centroid falls within `bbox`, most populous first, at most 1000 of
+    /// them. Used by the `/city/v1/inBounds` endpoint for map-driven clients that pan/zoom their
+    /// viewport.
+    pub(crate) async fn get_cities_in_bounds(&self, bbox: BoundingBox) -> HandlerResult<Vec<ElasticCity>> {
+        let (cities, _total_hits, _facet_counts) = self
+            .search_city(
+                json!({
+                    "query": {
+                        "bool": {
+                            "filter": [{
+                                "geo_bounding_box": {
+                                    "centroid": bbox.es_bounding_box()
+                                }
+                            }]
+                        }
+                    },
+                    "sort": [{ "population": "desc" }],
+                }),
+                0,
+                1000,
+                None,
+                &Facets::default(),
+            )
+            .await?;
+
+        Ok(cities)
+    }
+
+    /// Suggest cities matching a short, as-you-type `fragment`, ranked for prefix completion.
+    /// Backed by the Elasticsearch completion suggester, so this is a single round-trip with no
+    /// region fetch, unlike [LocationsElasticRepository::search] results, whose regions are
+    /// fetched afterwards to build [crate::handlers::city::CityResponse]s.
+    pub(crate) async fn suggest(
+        &self,
+        fragment: &str,
+        language: Language,
+        country_iso: Option<&str>,
+    ) -> HandlerResult<Vec<ElasticCitySuggestion>> {
+        let name_key = language.name_key();
+        let suggest_field = format!("{}.suggest", name_key);
+
+        let mut completion = json!({
+            "field": suggest_field,
+            "size": SUGGEST_RESULT_LIMIT,
+            "skip_duplicates": true,
+        });
+        if let Some(iso_code) = country_iso {
+            completion["contexts"] = json!({ "countryIso": [iso_code] });
+        }
+
+        let mut source_fields = vec!["id".to_owned(), "countryIso".to_owned()];
+        source_fields.extend(language.fallback_keys());
+
+        let body = json!({
+            "_source": source_fields,
+            "suggest": {
+                SUGGEST_NAME: {
+                    "prefix": fragment,
+                    "completion": completion,
+                }
+            }
+        });
+
+        let es = self.0.elasticsearch();
+        let response = es.search(Index(&[CITY_INDEX])).body(&body).send().await?;
+        let response = self.logged_error_for_status(Some(&body), response).await?;
+        let mut response_body = response.json::<SuggestApiResponse<ElasticCitySuggestion>>().await?;
+        debug!("Elasticsearch response body: {:?}.", response_body);
+
+        let entries = response_body.suggest.remove(SUGGEST_NAME).unwrap_or_default();
+        Ok(entries.into_iter().flat_map(|entry| entry.options).map(|option| option._source).collect())
+    }
+
+    /// Get a city closest to given geo `coords`, optionally filter by `is_featured` and/or
+    /// restrict candidates to a `boundary` (rectangle, circle or country) before the distance sort.
     pub(crate) async fn get_closest_city(
         &self,
         coords: Coordinates,
         is_featured: Option<bool>,
+        boundary: Option<Boundary>,
     ) -> HandlerResult<ElasticCity> {
+        let mut filters: Vec<JsonValue> = Vec::new();
+        if let Some(boundary) = boundary {
+            filters.push(boundary.es_filter());
+        }
+
         let query = json!({
             "query": {
                 "bool": {
@@ -190,6 +665,7 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
                         // `minimum_should_match`.
                         None => json!({"match_all": {}}),
                     },
+                    "filter": filters,
                     // Boost cities intersecting with `coords`.
                     "should": {
                         "geo_shape": {
@@ -213,10 +689,17 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
                 },
             ]
         });
-        let cities = self.search_city(query, 1).await?;
+        let (cities, _total_hits, _facet_counts) = self.search_city(query, 0, 1, None, &Facets::default()).await?;
 
-        // Extract the single city from response. Both no and multiple cities are unexpected.
-        cities.into_iter().single().map_err(|e| InternalServerError(e.to_string()))
+        // Extract the single city from response. No matches is expected whenever `boundary`
+        // excludes every city (e.g. a tiny circle or a country with none), multiple is unexpected
+        // since Elasticsearch was asked for at most one hit.
+        cities.into_iter().single().map_err(|e| match e {
+            single::Error::NoElements => {
+                ErrorResponse::not_found("No city matches the given constraints.", "city_not_found")
+            }
+            single::Error::MultipleElements => InternalServerError(e.to_string()),
+        })
     }
 
     async fn get_entity<T: fmt::Debug + DeserializeOwned>(
@@ -234,7 +717,8 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
             .await?;
 
         if response.status_code() == StatusCode::NOT_FOUND {
-            return Err(NotFound(format!("{}#{} not found.", entity_name, id)));
+            let code = format!("{}_not_found", entity_name.to_lowercase());
+            return Err(ErrorResponse::not_found(format!("{}#{} not found.", entity_name, id), &code));
         }
 
         let response = self.logged_error_for_status(None, response).await?;
@@ -244,13 +728,70 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
         Ok(response_body)
     }
 
-    async fn search_city(&self, body: JsonValue, size: i64) -> HandlerResult<Vec<ElasticCity>> {
+    /// Fetch multiple documents of type `T` from `index_name` by `ids` with a single
+    /// Elasticsearch multi-get (`_mget`) request. Documents with no match are absent from the
+    /// returned map; order is not preserved.
+    async fn mget_entities<T: fmt::Debug + DeserializeOwned>(
+        &self,
+        ids: &[u64],
+        index_name: &str,
+    ) -> HandlerResult<HashMap<u64, T>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let es = self.0.elasticsearch();
+        let id_strings: Vec<String> = ids.iter().map(u64::to_string).collect();
+        let body = json!({ "ids": id_strings });
+
+        let response = es
+            .mget(MgetParts::Index(index_name))
+            ._source_excludes(EXCLUDED_FIELDS)
+            .body(&body)
+            .send()
+            .await?;
+        let response = self.logged_error_for_status(Some(&body), response).await?;
+        let response_body = response.json::<MgetResponse<T>>().await?;
+        debug!("Elasticsearch response body: {:?}.", response_body);
+
+        Ok(response_body
+            .docs
+            .into_iter()
+            .filter_map(|doc| {
+                if !doc.found {
+                    return None;
+                }
+                let id = doc.id.parse().ok()?;
+                doc._source.map(|source| (id, source))
+            })
+            .collect())
+    }
+
+    /// Run a city search `body`, skipping `from` hits and returning at most `size` of the rest.
+    /// When `highlight` is given as `(field, options)`, populates [ElasticCity::highlightName] of
+    /// each hit from its highlighted `field` fragment. When `facets` is non-empty, adds an `aggs`
+    /// clause to `body` and returns the resulting bucket counts, keyed by facet field name and
+    /// then by bucket value. Returns the matched cities alongside the total number of hits
+    /// matching `body` (`hits.total.value`), regardless of `from`/`size`.
+    async fn search_city(
+        &self,
+        mut body: JsonValue,
+        from: i64,
+        size: i64,
+        highlight: Option<(&str, &HighlightOptions)>,
+        facets: &Facets,
+    ) -> HandlerResult<(Vec<ElasticCity>, u64, HashMap<String, HashMap<String, u64>>)> {
+        if !facets.is_empty() {
+            body["aggs"] = facets.es_aggs();
+        }
+
         let es = self.0.elasticsearch();
 
         let response = es
             .search(Index(&[CITY_INDEX]))
             .body(&body)
             ._source_excludes(EXCLUDED_FIELDS)
+            .from(from)
             .size(size)
             .send()
             .await?;
@@ -258,7 +799,35 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
         let response_body = response.json::<SearchResponse<ElasticCity>>().await?;
         debug!("Elasticsearch response body: {:?}.", response_body);
 
-        Ok(response_body.hits.hits.into_iter().map(|hit| hit._source).collect())
+        let total_hits = response_body.hits.total.value;
+        let cities = response_body
+            .hits
+            .hits
+            .into_iter()
+            .map(|hit| {
+                let mut city = hit._source;
+                if let Some((field, options)) = highlight {
+                    city.highlightName = hit
+                        .highlight
+                        .and_then(|mut fragments| fragments.remove(field))
+                        .and_then(|fragments| fragments.into_iter().next())
+                        .map(|fragment| options.apply_crop_marker(fragment));
+                }
+                city
+            })
+            .collect();
+
+        let facet_counts = response_body
+            .aggregations
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(field, agg)| {
+                let counts = agg.buckets.into_iter().map(|bucket| (bucket.key_string(), bucket.doc_count)).collect();
+                (field, counts)
+            })
+            .collect();
+
+        Ok((cities, total_hits, facet_counts))
     }
 
     async fn logged_error_for_status(
@@ -283,7 +852,7 @@ impl<S: WithElastic> LocationsElasticRepository<'_, S> {
 
 /// City entity mapped from Elasticsearch.
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub(crate) struct ElasticCity {
     pub(crate) id: u64,
     pub(crate) regionId: u64,
@@ -293,6 +862,24 @@ pub(crate) struct ElasticCity {
 
     #[serde(flatten)] // captures rest of fields, see https://serde.rs/attr-flatten.html
     pub(crate) names: HashMap<String, String>,
+
+    /// Highlighted fragment of the searched-for name, filled in by [LocationsElasticRepository::search]
+    /// from the Elasticsearch `highlight` response; absent (and not part of the source document)
+    /// for any other lookup.
+    #[serde(skip)]
+    pub(crate) highlightName: Option<String>,
+}
+
+/// Lightweight city entity returned by the completion suggester, kept separate from [ElasticCity]
+/// so `/city/v1/suggest` avoids the region join incurred by [crate::handlers::city::CityResponse].
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize)]
+pub(crate) struct ElasticCitySuggestion {
+    pub(crate) id: u64,
+    pub(crate) countryIso: String,
+
+    #[serde(flatten)] // captures rest of fields, see https://serde.rs/attr-flatten.html
+    pub(crate) names: HashMap<String, String>,
 }
 
 /// Region entity mapped from Elasticsearch.
@@ -309,14 +896,136 @@ pub(crate) struct ElasticRegion {
 #[derive(Debug, Deserialize)]
 struct SearchResponse<T> {
     hits: HitsResponse<T>,
+    /// Per-facet `terms` aggregation buckets, present only when the search request included an
+    /// `aggs` clause. Keyed by facet field name, see [Facets::es_aggs].
+    #[serde(default)]
+    aggregations: Option<HashMap<String, TermsAggregation>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TermsAggregation {
+    buckets: Vec<TermsBucket>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TermsBucket {
+    key: JsonValue,
+    doc_count: u64,
+}
+
+impl TermsBucket {
+    /// Stringify this bucket's key, which Elasticsearch may return as a JSON string (e.g. for
+    /// `countryIso`) or a number (e.g. for `regionId`).
+    fn key_string(&self) -> String {
+        match &self.key {
+            JsonValue::String(key) => key.clone(),
+            other => other.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct HitsResponse<T> {
     hits: Vec<Hit<T>>,
+    total: HitsTotal,
+}
+
+#[derive(Debug, Deserialize)]
+struct HitsTotal {
+    value: u64,
 }
 
 #[derive(Debug, Deserialize)]
 struct Hit<T> {
     _source: T,
+    /// Per-field highlighted fragments, present only when the search request included a
+    /// `highlight` clause. Keyed by the highlighted field name, e.g. `"name.en.autocomplete"`.
+    #[serde(default)]
+    highlight: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MgetResponse<T> {
+    docs: Vec<MgetDoc<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MgetDoc<T> {
+    #[serde(rename = "_id")]
+    id: String,
+    found: bool,
+    #[serde(default)]
+    _source: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestApiResponse<T> {
+    suggest: HashMap<String, Vec<SuggestEntry<T>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestEntry<T> {
+    options: Vec<Hit<T>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pagination_from_page_rejects_offset_overflow() {
+        let err = Pagination::from_page(u32::MAX, 200).unwrap_err();
+        assert!(matches!(err, ErrorResponse::BadRequest { code, .. } if code == "invalid_page"));
+    }
+
+    #[test]
+    fn pagination_from_page_computes_offset() {
+        let pagination = Pagination::from_page(3, 20).unwrap();
+        assert_eq!(pagination.offset, 40);
+        assert_eq!(pagination.limit, 20);
+    }
+
+    #[test]
+    fn pagination_from_page_rejects_limit_over_max() {
+        let err = Pagination::from_page(1, MAX_PAGE_LIMIT + 1).unwrap_err();
+        assert!(matches!(err, ErrorResponse::BadRequest { code, .. } if code == "invalid_limit"));
+    }
+
+    #[test]
+    fn matching_strategy_parse_accepts_known_values() {
+        assert_eq!(MatchingStrategy::parse("all").unwrap(), MatchingStrategy::All);
+        assert_eq!(MatchingStrategy::parse("last").unwrap(), MatchingStrategy::Last);
+    }
+
+    #[test]
+    fn matching_strategy_parse_rejects_unknown_value() {
+        let err = MatchingStrategy::parse("bogus").unwrap_err();
+        assert!(matches!(err, ErrorResponse::BadRequest { code, .. } if code == "invalid_matching_strategy"));
+    }
+
+    #[test]
+    fn facets_new_rejects_unknown_field() {
+        let err = Facets::new(vec!["bogus".to_owned()]).unwrap_err();
+        assert!(matches!(err, ErrorResponse::BadRequest { code, .. } if code == "invalid_facet"));
+    }
+
+    #[test]
+    fn facets_new_rejects_too_many_fields() {
+        let fields = vec!["countryIso".to_owned(); MAX_FACET_FIELDS + 1];
+        let err = Facets::new(fields).unwrap_err();
+        assert!(matches!(err, ErrorResponse::BadRequest { code, .. } if code == "too_many_facets"));
+    }
+
+    #[test]
+    fn bounding_box_new_rejects_inverted_box() {
+        let err = BoundingBox::new(10.0, 10.0, 5.0, 20.0).unwrap_err();
+        assert!(matches!(err, ErrorResponse::BadRequest { code, .. } if code == "invalid_bounding_box"));
+    }
+
+    #[test]
+    fn bounding_box_new_accepts_valid_box() {
+        let bbox = BoundingBox::new(5.0, 10.0, 15.0, 20.0).unwrap();
+        assert_eq!(bbox.minLat, 5.0);
+        assert_eq!(bbox.maxLat, 15.0);
+    }
 }
@@ -0,0 +1,90 @@
+//! In-memory, TTL-bounded cache for read-heavy endpoint responses.
+
+use dashmap::DashMap;
+use std::{
+    collections::hash_map::DefaultHasher,
+    env,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Number of inserts between opportunistic sweeps of expired entries (see
+/// [ResponseCache::get_or_compute]), bounding the cache's otherwise-unbounded growth without the
+/// overhead of a sweep on every single insert.
+const SWEEP_EVERY_N_INSERTS: u64 = 128;
+
+/// Env var controlling how long cached entries (and the `Cache-Control: max-age` we emit for
+/// them) stay fresh, in seconds.
+const CACHE_TTL_SECS_ENV: &str = "GOOUT_ELASTIC_CACHE_TTL_SECS";
+const DEFAULT_TTL_SECS: u64 = 60;
+
+/// TTL read from [CACHE_TTL_SECS_ENV], falling back to [DEFAULT_TTL_SECS] if unset or invalid.
+pub(crate) fn ttl_secs() -> u64 {
+    env::var(CACHE_TTL_SECS_ENV).ok().and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_TTL_SECS)
+}
+
+/// An already-serialized JSON response body, plus a content-derived ETag, ready to be sent back
+/// to the client or compared against an `If-None-Match` request header.
+#[derive(Clone, Debug)]
+pub(crate) struct CachedResponse {
+    pub(crate) body: String,
+    pub(crate) etag: String,
+}
+
+impl CachedResponse {
+    fn new(body: String) -> Self {
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        let etag = format!("\"{:x}\"", hasher.finish());
+        Self { body, etag }
+    }
+}
+
+/// A TTL-bounded in-memory cache of [CachedResponse]s, keyed by `K`. Entries older than
+/// [ttl_secs] are recomputed on next access; to keep the otherwise-unbounded key space (e.g.
+/// `(city_id, Language)`) from growing memory forever, expired entries are also swept out
+/// periodically, every [SWEEP_EVERY_N_INSERTS] inserts.
+pub(crate) struct ResponseCache<K> {
+    entries: DashMap<K, (Instant, CachedResponse)>,
+    inserts: AtomicU64,
+}
+
+impl<K: Hash + Eq> ResponseCache<K> {
+    pub(crate) fn new() -> Self {
+        Self { entries: DashMap::new(), inserts: AtomicU64::new(0) }
+    }
+
+    /// Return the cached entry for `key` if present and still fresh, otherwise `compute` the
+    /// serialized JSON body, derive its ETag and store the result (keyed by `key`) before
+    /// returning it.
+    pub(crate) async fn get_or_compute<F, Fut, E>(&self, key: K, compute: F) -> Result<CachedResponse, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String, E>>,
+    {
+        if let Some(entry) = self.entries.get(&key) {
+            let (inserted_at, cached) = entry.value();
+            if inserted_at.elapsed() < Duration::from_secs(ttl_secs()) {
+                return Ok(cached.clone());
+            }
+        }
+
+        let body = compute().await?;
+        let cached = CachedResponse::new(body);
+        self.entries.insert(key, (Instant::now(), cached.clone()));
+
+        if self.inserts.fetch_add(1, Ordering::Relaxed) % SWEEP_EVERY_N_INSERTS == 0 {
+            self.sweep_expired();
+        }
+
+        Ok(cached)
+    }
+
+    /// Drop every entry older than [ttl_secs], bounding memory use for key spaces that are
+    /// effectively unbounded (e.g. every `(city_id, Language)` pair ever looked up).
+    fn sweep_expired(&self) {
+        let ttl = Duration::from_secs(ttl_secs());
+        self.entries.retain(|_, (inserted_at, _)| inserted_at.elapsed() < ttl);
+    }
+}